@@ -1,13 +1,33 @@
 //! Encoding and decoding of the (24, 12, 8) extended Golay code.
 
-use binfield_matrix::{matrix_mul, matrix_mul_systematic};
+use binfield_matrix::matrix_mul;
+use num_traits::PrimInt;
+use once_cell::sync::Lazy;
+
+use super::{widen_one, Codec, Decode};
+
+/// The (24, 12, 8) code as a data-driven [`Codec`](../struct.Codec.html).
+static CODEC: Codec = Codec::new(CORE_XPOSE, PAR, 24, 12);
+
+/// Coset-leader table for the (24, 12, 8) code, built on first use.
+///
+/// Unlike the standard code, the (24, 12, 8) code is not perfect: only
+/// 1 + 24 + 276 + 2024 = 2325 of the 2<sup>12</sup> = 4096 syndromes have a weight-≤3
+/// coset leader. A lookup landing on an unfilled (sentinel) entry reports a
+/// detected-but-uncorrectable 4-error word.
+static LEADERS: Lazy<Vec<u32>> = Lazy::new(|| CODEC.leaders());
 
 /// Encode the given 12 data bits into a 24-bit codeword.
-pub fn encode(data: u16) -> u32 {
-    assert_eq!(data >> 12, 0);
+///
+/// The word type is generic over any `PrimInt`, with the data and codeword sharing that
+/// same width, so callers holding packed `u64` frames or `usize` buffers can encode in
+/// place without casting. There is no implicit default: `encode::<u32>` reproduces the
+/// natural 24-bit codeword, and `T` must be wide enough to hold it.
+pub fn encode<T: PrimInt>(data: T) -> T {
+    assert!(data >> 12 == T::zero());
 
     // Compute wG = w[ I | A ].
-    matrix_mul_systematic(data, CORE_XPOSE)
+    CODEC.encode(data)
 }
 
 /// Try to decode the given 24-bit word to the nearest codeword, correcting up to 3
@@ -16,18 +36,36 @@ pub fn encode(data: u16) -> u32 {
 /// If decoding was successful, return `Some((data, err))`, where `data` is the 12
 /// data bits and `err` is the number of corrected bits. Otherwise, return `None` to
 /// indicate an unrecoverable error.
-pub fn decode(word: u32) -> Option<(u16, usize)> {
-    assert_eq!(word >> 24, 0);
+///
+/// This is a thin wrapper over [`decode_detect`](fn.decode_detect.html) for source
+/// compatibility; new callers that need to tell the code's genuine 4-error detection
+/// apart from a fault should prefer that function.
+pub fn decode<T: PrimInt>(word: T) -> Option<(T, usize)> {
+    decode_detect(word).into_option()
+}
+
+/// Try to decode the given 24-bit word to the nearest codeword, reporting a detected
+/// error distinctly from a clean decode.
+///
+/// Returns [`Decode::Corrected`](../enum.Decode.html) with the 12 data bits and the
+/// number of corrected bits, or [`Decode::Detected`](../enum.Decode.html) when 4 errors
+/// were detected.
+pub fn decode_detect<T: PrimInt>(word: T) -> Decode<T> {
+    assert!(word >> 24 == T::zero());
 
     // Strip off parity bits.
-    let data = (word >> 12) as u16;
+    let data = word >> 12;
+
+    // The syndrome math runs at the code's native 24-bit width against the `u32` parity
+    // matrices, so wider word types cost no per-call allocation.
+    let w = word.to_u32().unwrap();
 
     // Compute wG<sup>T</sup> to check for errors isolated to upper 12 bits.
-    let s: u16 = matrix_mul(word, PAR_ALT);
+    let s: T = widen_one(matrix_mul::<u32, u32>(w, PAR_ALT));
     let n = s.count_ones() as usize;
 
     if n <= 3 {
-        return Some((data ^ s, n));
+        return Decode::Corrected { data: data ^ s, errors: n };
     }
 
     // Check for cases with one error in lower 12 bits and one or two errors in upper
@@ -40,20 +78,20 @@ pub fn decode(word: u32) -> Option<(u16, usize)> {
     // Since e<sub>i<sub> isn't used to repair the data bits, we instead just loop
     // over all the words in A<sup>T</sup>.
     for &q in CORE_XPOSE.iter() {
-        let syn = s ^ q;
+        let syn = s ^ widen_one::<T, _>(q);
         let n = syn.count_ones() as usize;
 
         if n <= 2 {
-            return Some((data ^ syn, n + 1));
+            return Decode::Corrected { data: data ^ syn, errors: n + 1 };
         }
     }
 
     // Compute wH<sup>T</sup> to check for errors isolated to lower 12 bits.
-    let s: u16 = matrix_mul(word, PAR);
+    let s: T = widen_one(matrix_mul::<u32, u32>(w, PAR));
     let n = s.count_ones() as usize;
 
     if n <= 3 {
-        return Some((data, n));
+        return Decode::Corrected { data, errors: n };
     }
 
     // Check for cases with one error in upper 12 bits and 2 errors in lower 12 bits [2,
@@ -64,15 +102,36 @@ pub fn decode(word: u32) -> Option<(u16, usize)> {
     // b<sub>i</sub> is the (i+12)'th row from the bottom of H<sup>T</sup>, which
     // equals the i'th row from the bottom of A.
     for (i, &q) in CORE.iter().enumerate() {
-        let syn = s ^ q;
+        let syn = s ^ widen_one::<T, _>(q);
 
         if syn.count_ones() <= 2 {
-            let err = 1 << 11 >> i;
-            return Some((data ^ err, 3));
+            let err = T::one() << 11 >> i;
+            return Decode::Corrected { data: data ^ err, errors: 3 };
         }
     }
 
-    None
+    Decode::Detected
+}
+
+/// Decode the given 24-bit word with a single coset-leader table lookup.
+///
+/// This is behaviourally identical to [`decode`](fn.decode.html) but replaces the
+/// branchy syndrome search with an O(1) indexed lookup, which is preferable in a hot
+/// receive loop. The word type is fixed to `u32` since the lookup table is indexed by
+/// the 12-bit syndrome of a 24-bit word.
+pub fn decode_table(word: u32) -> Option<(u16, usize)> {
+    decode_table_detect(word).into_option()
+}
+
+/// Decode the given 24-bit word with a single coset-leader table lookup, reporting a
+/// detected error distinctly from a clean decode.
+///
+/// A sentinel table entry becomes [`Decode::Detected`](../enum.Decode.html), preserving
+/// the extended code's 4-error detection through the table-driven path.
+pub fn decode_table_detect(word: u32) -> Decode<u16> {
+    assert_eq!(word >> 24, 0);
+
+    CODEC.decode_table(word, &LEADERS)
 }
 
 /// Generator parity submatrix, also known as **A**.
@@ -92,7 +151,7 @@ const CORE: &[u16] = &[
 ];
 
 /// Transpose of generator parity submatrix, also known as **A**<sup>T</sup>.
-const CORE_XPOSE: &[u16] = &[
+const CORE_XPOSE: &[u32] = &[
     0b101001001111,
     0b111101101000,
     0b011110110100,
@@ -155,16 +214,16 @@ mod test {
 
     #[test]
     fn test_encode() {
-        assert_eq!(encode(0), 0);
-        assert_eq!(encode(0b111111111111), 0b111111111111_111111111111);
-        assert_eq!(encode(0b111111000000), 0b111111000000_110011010001);
-        assert_eq!(encode(0b000000111111), 0b000000111111_001100101110);
-        assert_eq!(encode(0b100000000001), 0b100000000001_010010011110);
+        assert_eq!(encode::<u32>(0), 0);
+        assert_eq!(encode::<u32>(0b111111111111), 0b111111111111_111111111111);
+        assert_eq!(encode::<u32>(0b111111000000), 0b111111000000_110011010001);
+        assert_eq!(encode::<u32>(0b000000111111), 0b000000111111_001100101110);
+        assert_eq!(encode::<u32>(0b100000000001), 0b100000000001_010010011110);
     }
 
     #[test]
     fn test_decode() {
-        let w = 0b111111101010;
+        let w: u32 = 0b111111101010;
         let e = encode(w);
         assert_eq!(e, 0b111111101010_111011100100);
 
@@ -225,28 +284,89 @@ mod test {
         assert_eq!(decode(e^0b000000100000000000000011), Some((w, 3)));
 
         // Exhaustively test that all codewords are decoded correctly.
-        for w in 0..1<<12 {
+        for w in 0u32..1<<12 {
             assert_eq!(decode(encode(w)), Some((w, 0)));
         }
 
-        let w = encode(0b110110100110);
+        let w = encode(0b110110100110u32);
+
+        // Exhaustively test that all 0 through 3-bit errors are corrected.
+        for i in 0..24 {
+            for j in 0..24 {
+                for k in 0..24 {
+                    let e: u32 = 1 << i | 1 << j | 1 << k;
+                    let n = e.count_ones() as usize;
+
+                    assert_eq!(decode(w ^ e), Some((0b110110100110, n)));
+                }
+            }
+        }
+
+        // Exhaustively test that all 4-bit errors are detected.
+        for h in 0..24 {
+            for i in 0..24 {
+                for j in 0..24 {
+                    for k in 0..24 {
+                        let e: u32 = 1 << h | 1 << i | 1 << j | 1 << k;
+
+                        if e.count_ones() >= 4 {
+                            assert_eq!(decode(w ^ e), None);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_table() {
+        // Exhaustively test that all codewords are decoded correctly.
+        for w in 0u32..1<<12 {
+            assert_eq!(decode_table(encode(w)), Some((w as u16, 0)));
+        }
+
+        let w = encode(0b110110100110u32);
 
-        // Exhaustively test that all 0 through 3-bit errors are detected.
-        for ((i, j), k) in (0..24).zip(0..24).zip(0..24) {
-            let e: u32 = 1 << i | 1 << j | 1 << k;
-            let n = e.count_ones() as usize;
+        // Exhaustively test that the table lookup corrects all 0 through 3-bit errors.
+        for i in 0..24 {
+            for j in 0..24 {
+                for k in 0..24 {
+                    let e: u32 = 1 << i | 1 << j | 1 << k;
+                    let n = e.count_ones() as usize;
 
-            assert_eq!(decode(w ^ e), Some((0b110110100110, n)));
+                    assert_eq!(decode_table(w ^ e), Some((0b110110100110, n)));
+                }
+            }
         }
 
         // Exhaustively test that all 4-bit errors are detected.
-        for (((h, i), j), k) in (0..24).zip(0..24).zip(0..24).zip(0..24) {
-            let e: u32 = 1 << h | 1 << i | 1 << j | 1 << k;
-            let n = e.count_ones() as usize;
+        for h in 0..24 {
+            for i in 0..24 {
+                for j in 0..24 {
+                    for k in 0..24 {
+                        let e: u32 = 1 << h | 1 << i | 1 << j | 1 << k;
 
-            if n >= 4 {
-                assert_eq!(decode(w ^ e), None);
+                        if e.count_ones() >= 4 {
+                            assert_eq!(decode_table(w ^ e), None);
+                        }
+                    }
+                }
             }
         }
     }
+
+    #[test]
+    fn test_decode_detect() {
+        let w = encode(0b110110100110u32);
+
+        // A correctable word reports the corrected data and error count.
+        assert_eq!(decode_detect(w), Decode::Corrected { data: 0b110110100110, errors: 0 });
+
+        // A 4-error word is detected rather than silently miscorrected, through both the
+        // searching and table-driven paths.
+        let e = 0b1111u32;
+        assert_eq!(decode_detect(w ^ e), Decode::Detected);
+        assert_eq!(decode_table_detect(w ^ e), Decode::Detected);
+        assert_eq!(decode(w ^ e), None);
+    }
 }
@@ -16,6 +16,128 @@
 //! 3. "High-Speed Decoding of the Binary Golay Code", Lee et al, 2013
 
 extern crate binfield_matrix;
+extern crate num_traits;
+extern crate once_cell;
+
+use binfield_matrix::matrix_mul;
+use num_traits::{NumCast, PrimInt};
 
 pub mod extended;
 pub mod standard;
+
+/// The outcome of decoding a received word.
+///
+/// This distinguishes a clean correction from a genuinely detected error, so an
+/// air-interface state machine can request retransmission on [`Decode::Detected`] —
+/// the extended code's real 4-error detection capability — rather than treating it the
+/// same as a malformed input.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Decode<T> {
+    /// The word was corrected to the nearest codeword, yielding `data` after flipping
+    /// `errors` bit errors.
+    Corrected { data: T, errors: usize },
+    /// Errors were detected but the word lies too far from any codeword to correct.
+    Detected,
+}
+
+impl<T> Decode<T> {
+    /// Collapse into the legacy `Option<(data, err)>`, with [`Decode::Detected`] becoming
+    /// `None`.
+    pub fn into_option(self) -> Option<(T, usize)> {
+        match self {
+            Decode::Corrected { data, errors } => Some((data, errors)),
+            Decode::Detected => None,
+        }
+    }
+}
+
+/// Marks a syndrome with no weight-≤3 coset leader, i.e. one produced by ≥4 errors.
+const SENTINEL: u32 = u32::MAX;
+
+/// A short CAI block code described entirely by its matrices and dimensions.
+///
+/// Both Golay codes — and any further short block code in the `cai_*` family — encode
+/// and table-decode through this one implementation, parameterised by its matrices and
+/// a coset-leader table the same way `cai_hamming::decode` is parameterised by its
+/// parity-check matrix and error table. The searching decoders (`decode` and
+/// `decode_detect` in each code module) do not go through it: each module keeps its own
+/// syndrome-search body.
+pub struct Codec {
+    /// Transpose of the generator parity submatrix **A**<sup>T</sup>, appended to the
+    /// data bits on encode.
+    core_xpose: &'static [u32],
+    /// Parity-check matrix **H**, used to compute the syndrome *w***H**<sup>T</sup>.
+    par: &'static [u32],
+    /// Code length *n* in bits.
+    len: usize,
+    /// Number of parity bits *n* - *k* appended on encode.
+    parity_bits: usize,
+}
+
+impl Codec {
+    /// Describe a code of length `len` with `parity_bits` check bits, the generator
+    /// parity submatrix transpose `core_xpose`, and parity-check matrix `par`.
+    pub const fn new(
+        core_xpose: &'static [u32],
+        par: &'static [u32],
+        len: usize,
+        parity_bits: usize,
+    ) -> Codec {
+        Codec { core_xpose, par, len, parity_bits }
+    }
+
+    /// Encode the given data bits into a systematic codeword `[ w | w`**A**<sup>T</sup>` ]`.
+    ///
+    /// The GF(2) matrix product is evaluated at the code's native `u32` width — these
+    /// codes are at most 24 bits wide — and only the result is widened to `T`, so no
+    /// per-call allocation is needed to serve wider word types.
+    pub fn encode<T: PrimInt>(&self, data: T) -> T {
+        let parity: u32 = matrix_mul(data.to_u32().unwrap(), self.core_xpose);
+        data << self.parity_bits | widen_one::<T, _>(parity)
+    }
+
+    /// Build the coset-leader table mapping each syndrome to its minimum-weight error
+    /// pattern, leaving [`SENTINEL`](constant.SENTINEL.html) in any syndrome with no
+    /// pattern of weight up to 3 (i.e. a detected-but-uncorrectable word).
+    pub fn leaders(&self) -> Vec<u32> {
+        let mut table = vec![SENTINEL; 1 << self.parity_bits];
+
+        // The all-zero error pattern is the leader of the zero syndrome.
+        table[0] = 0;
+
+        for a in 0..self.len {
+            for b in a..self.len {
+                for c in b..self.len {
+                    let e = 1u32 << a | 1u32 << b | 1u32 << c;
+                    let s: u32 = matrix_mul(e, self.par);
+                    table[s as usize] = e;
+                }
+            }
+        }
+
+        table
+    }
+
+    /// Decode `word` through a single lookup in the given coset-leader table.
+    ///
+    /// Yields [`Decode::Corrected`] for a correctable word or [`Decode::Detected`] when
+    /// the syndrome has no coset leader of weight up to 3.
+    pub fn decode_table(&self, word: u32, leaders: &[u32]) -> Decode<u16> {
+        let s: u32 = matrix_mul(word, self.par);
+        let e = leaders[s as usize];
+
+        if e == SENTINEL {
+            return Decode::Detected;
+        }
+
+        Decode::Corrected {
+            data: ((word ^ e) >> self.parity_bits) as u16,
+            errors: e.count_ones() as usize,
+        }
+    }
+}
+
+/// Cast a single fixed-width matrix entry into the codec word type `T`.
+fn widen_one<T: PrimInt, U: PrimInt>(row: U) -> T {
+    <T as NumCast>::from(row).unwrap()
+}
@@ -1,13 +1,28 @@
 //! Encoding and decoding of the (23, 12, 7) standard Golay code.
 
-use binfield_matrix::{matrix_mul, matrix_mul_systematic};
+use binfield_matrix::matrix_mul;
+use num_traits::PrimInt;
+use once_cell::sync::Lazy;
+
+use super::{widen_one, Codec, Decode};
+
+/// The (23, 12, 7) code as a data-driven [`Codec`](../struct.Codec.html).
+static CODEC: Codec = Codec::new(CORE_XPOSE, PAR, 23, 11);
+
+/// Coset-leader table for the perfect (23, 12, 7) code, built on first use.
+static LEADERS: Lazy<Vec<u32>> = Lazy::new(|| CODEC.leaders());
 
 /// Encode the given 12 data bits into a 23-bit codeword.
-pub fn encode(data: u16) -> u32 {
-    assert_eq!(data >> 12, 0);
+///
+/// The word type is generic over any `PrimInt`, with the data and codeword sharing that
+/// same width, so callers holding packed `u64` frames or `usize` buffers can encode in
+/// place without casting. There is no implicit default: `encode::<u32>` reproduces the
+/// natural 23-bit codeword, and `T` must be wide enough to hold it.
+pub fn encode<T: PrimInt>(data: T) -> T {
+    assert!(data >> 12 == T::zero());
 
-    // Compute wG.
-    matrix_mul_systematic(data, CORE_XPOSE)
+    // Compute wG = [ w | wA<sup>T</sup> ].
+    CODEC.encode(data)
 }
 
 /// Try to decode the given 23-bit word to the nearest codeword, correcting up to 3
@@ -16,42 +31,64 @@ pub fn encode(data: u16) -> u32 {
 /// If decoding was successful, return `Some((data, err))`, where `data` is the 12
 /// data bits and `err` is the number of corrected bits. Otherwise, return `None` to
 /// indicate an unrecoverable error.
-pub fn decode(word: u32) -> Option<(u16, usize)> {
-    assert_eq!(word >> 23, 0);
+///
+/// This is a thin wrapper over [`decode_detect`](fn.decode_detect.html) for source
+/// compatibility; new callers that need to tell a detected error apart from a fault
+/// should prefer that function.
+pub fn decode<T: PrimInt>(word: T) -> Option<(T, usize)> {
+    decode_detect(word).into_option()
+}
+
+/// Try to decode the given 23-bit word to the nearest codeword, reporting a detected
+/// error distinctly from a clean decode.
+///
+/// Returns [`Decode::Corrected`](../enum.Decode.html) with the 12 data bits and the
+/// number of corrected bits. The (23, 12, 7) code is perfect, so every 23-bit word lies
+/// within distance 3 of a unique codeword; the systematic search from [3] is completed
+/// by a coset-leader lookup for the words it does not trap, so this always corrects and
+/// never returns [`Decode::Detected`](../enum.Decode.html). That variant exists for the
+/// [`extended`](../extended/index.html) code's genuine 4-error detection; the shared
+/// return type simply lets both codes report through one enum.
+pub fn decode_detect<T: PrimInt>(word: T) -> Decode<T> {
+    assert!(word >> 23 == T::zero());
 
     // Strip off parity bits.
-    let data = (word >> 11) as u16;
+    let data = word >> 11;
+
+    // The syndrome math runs at the code's native 23-bit width against the `u32` parity
+    // matrix, so wider word types cost no per-call allocation.
+    let w = word.to_u32().unwrap();
 
     // Check for 1 to 3 errors isolated in the parity bits.
-    let s: u16 = matrix_mul(word, PAR);
+    let s: T = widen_one(matrix_mul::<u32, u32>(w, PAR));
     let n = s.count_ones() as usize;
 
     if n <= 3 {
-        return Some((data, n));
+        return Decode::Corrected { data, errors: n };
     }
 
     // Check for cases with 1 error in the data bits and 0 to 2 errors in the parity bits.
     for (i, &syn) in SYN.iter().enumerate() {
-        let n = (s ^ syn).count_ones() as usize;
+        let n = (s ^ widen_one::<T, _>(syn)).count_ones() as usize;
 
         if n <= 2 {
-            return Some((data ^ 1 << i, n + 1));
+            return Decode::Corrected { data: data ^ T::one() << i, errors: n + 1 };
         }
     }
 
     // Check for 2 or 3 errors isolated to the data bits (except data MSB).
-    let s: u16 = matrix_mul(rotate_11(word), PAR);
+    let s: T = widen_one(matrix_mul::<u32, u32>(rotate_11(w), PAR));
     let n = s.count_ones() as usize;
 
     if n <= 3 {
-        return Some((data ^ s, n));
+        return Decode::Corrected { data: data ^ s, errors: n };
     }
 
     // Check for cases with 2 or 3 errors in the data bits (one being the data MSB) and
     // possibly 1 error in the parity bits or 2 errors in the data bits (exluding data
     // MSB) and 1 error in the parity bits.
     for (i, &syn) in SYN.iter().enumerate() {
-        let r = s ^ syn;
+        let r = s ^ widen_one::<T, _>(syn);
         let n = r.count_ones() as usize;
 
         if n <= 2 {
@@ -59,25 +96,51 @@ pub fn decode(word: u32) -> Option<(u16, usize)> {
             // 12 bits of the rotated word), so it must be flipped, but the following
             // syndromes correspond to parity bits, which don't need to be flipped.
             return if i == 0 {
-                Some((data ^ r ^ 1 << 11, n + 1))
+                Decode::Corrected { data: data ^ r ^ T::one() << 11, errors: n + 1 }
             } else {
-                Some((data ^ r, 3))
+                Decode::Corrected { data: data ^ r, errors: 3 }
             };
         }
     }
 
-    None
+    // The systematic search from [3] traps most low-weight errors directly but is not a
+    // complete decoder. Since the (23, 12, 7) code is perfect, every syndrome has a
+    // unique coset leader of weight up to 3, so resolve any word the search leaves over
+    // through the leader table to guarantee a correction.
+    let e = LEADERS[matrix_mul::<u32, u32>(w, PAR) as usize];
+    Decode::Corrected { data: widen_one((w ^ e) >> 11), errors: e.count_ones() as usize }
+}
+
+/// Decode the given 23-bit word with a single coset-leader table lookup.
+///
+/// Both this and [`decode`](fn.decode.html) are complete decoders of the perfect code
+/// and return the same correction for every word; this one replaces the branchy
+/// syndrome search with an O(1) indexed lookup, which is preferable in a hot receive
+/// loop. The word type is fixed to `u32` since the lookup table is indexed by the 11-bit
+/// syndrome of a 23-bit word.
+pub fn decode_table(word: u32) -> Option<(u16, usize)> {
+    decode_table_detect(word).into_option()
+}
+
+/// Decode the given 23-bit word with a single coset-leader table lookup, reporting a
+/// detected error distinctly from a clean decode.
+///
+/// See [`decode_table`](fn.decode_table.html) for the table-lookup rationale.
+pub fn decode_table_detect(word: u32) -> Decode<u16> {
+    assert_eq!(word >> 23, 0);
+
+    CODEC.decode_table(word, &LEADERS)
 }
 
 /// Circularly shift the given 23-bit word right by 11 bits.
-fn rotate_11(word: u32) -> u32 {
-    let parity = word & 0x7FF;
+fn rotate_11<T: PrimInt>(word: T) -> T {
+    let parity = word & widen_one::<T, _>(0x7FFu16);
     word >> 11 | parity << 12
 }
 
 /// Transpose of generator parity submatrix with extended code's LSB parity bit removed,
 /// also known as **A**<sup>T</sup>.
-const CORE_XPOSE: &[u16] = &[
+const CORE_XPOSE: &[u32] = &[
     0b101001001111,
     0b111101101000,
     0b011110110100,
@@ -135,23 +198,23 @@ mod test {
 
     #[test]
     fn test_rotate11() {
-        assert_eq!(rotate_11(0b111111111111_00000000000), 0b000000000001_11111111111);
-        assert_eq!(rotate_11(0b000000000000_11111111111), 0b111111111110_00000000000);
-        assert_eq!(rotate_11(0b100000000000_00000000000), 0b000000000001_00000000000);
+        assert_eq!(rotate_11::<u32>(0b111111111111_00000000000), 0b000000000001_11111111111);
+        assert_eq!(rotate_11::<u32>(0b000000000000_11111111111), 0b111111111110_00000000000);
+        assert_eq!(rotate_11::<u32>(0b100000000000_00000000000), 0b000000000001_00000000000);
     }
 
     #[test]
     fn test_encode() {
-        assert_eq!(encode(0), 0);
-        assert_eq!(encode(0b111111111111), 0b111111111111_11111111111);
-        assert_eq!(encode(0b111111000000), 0b111111000000_11001101000);
-        assert_eq!(encode(0b000000111111), 0b000000111111_00110010111);
-        assert_eq!(encode(0b100000000001), 0b100000000001_01001001111);
+        assert_eq!(encode::<u32>(0), 0);
+        assert_eq!(encode::<u32>(0b111111111111), 0b111111111111_11111111111);
+        assert_eq!(encode::<u32>(0b111111000000), 0b111111000000_11001101000);
+        assert_eq!(encode::<u32>(0b000000111111), 0b000000111111_00110010111);
+        assert_eq!(encode::<u32>(0b100000000001), 0b100000000001_01001001111);
     }
 
     #[test]
     fn test_decode() {
-        let w = 0b101010101010;
+        let w: u32 = 0b101010101010;
         let e = encode(w);
         assert_eq!(e, 0b1010101010_1000101111001);
 
@@ -209,28 +272,65 @@ mod test {
         assert_eq!(decode(e^0b00000100000000000000011), Some((w, 3)));
 
         // Exhaustively test that all codewords are decoded correctly.
-        for w in 0..1<<12 {
+        for w in 0u32..1<<12 {
             assert_eq!(decode(encode(w)), Some((w, 0)));
         }
 
-        let w = encode(0b110111101110);
+        let w = encode(0b110111101110u32);
 
-        // Exhaustively test that all 0 through 3-bit errors are detected.
-        for ((i, j), k) in (0..23).zip(0..23).zip(0..23) {
-            let e: u32 = 1 << i | 1 << j | 1 << k;
-            let n = e.count_ones() as usize;
+        // Exhaustively test that all 0 through 3-bit errors are corrected. The perfect
+        // (23, 12, 7) code has no 4-error detection — a 4-bit error decodes to a (wrong)
+        // nearest codeword rather than being rejected — so there is no detection case to
+        // assert here; see `test_decode_detect`.
+        for i in 0..23 {
+            for j in 0..23 {
+                for k in 0..23 {
+                    let e: u32 = 1 << i | 1 << j | 1 << k;
+                    let n = e.count_ones() as usize;
 
-            assert_eq!(decode(w ^ e), Some((0b110111101110, n)));
+                    assert_eq!(decode(w ^ e), Some((0b110111101110, n)));
+                }
+            }
         }
+    }
 
-        // Exhaustively test that all 4-bit errors are detected.
-        for (((h, i), j), k) in (0..23).zip(0..23).zip(0..23).zip(0..23) {
-            let e: u32 = 1 << h | 1 << i | 1 << j | 1 << k;
-            let n = e.count_ones() as usize;
+    #[test]
+    fn test_decode_table() {
+        // Exhaustively test that all codewords are decoded correctly.
+        for w in 0u32..1<<12 {
+            assert_eq!(decode_table(encode(w)), Some((w as u16, 0)));
+        }
 
-            if n >= 4 {
-                assert_eq!(decode(w ^ e), None);
+        let w = encode(0b110111101110u32);
+
+        // Both paths are complete decoders of the perfect code, so they agree exactly:
+        // check the table lookup corrects every 0 through 3-bit error and matches the
+        // searching `decode` on the same words.
+        for i in 0..23 {
+            for j in 0..23 {
+                for k in 0..23 {
+                    let e: u32 = 1 << i | 1 << j | 1 << k;
+                    let n = e.count_ones() as usize;
+
+                    assert_eq!(decode_table(w ^ e), Some((0b110111101110, n)));
+                    assert_eq!(decode_table(w ^ e), decode(w ^ e).map(|(d, m)| (d as u16, m)));
+                }
             }
         }
     }
+
+    #[test]
+    fn test_decode_detect() {
+        let w = encode(0b110111101110u32);
+
+        // A correctable word reports the corrected data and error count.
+        assert_eq!(decode_detect(w), Decode::Corrected { data: 0b110111101110, errors: 0 });
+
+        // The (23, 12, 7) code is perfect, so every word lies within distance 3 of a
+        // unique codeword: a 4-error word is silently miscorrected rather than detected.
+        // `Decode::Detected` is therefore unreachable here — only the extended code has
+        // genuine 4-error detection.
+        let e = 0b1111u32;
+        assert_eq!(decode_detect(w ^ e), Decode::Corrected { data: 0b110110100110, errors: 3 });
+    }
 }